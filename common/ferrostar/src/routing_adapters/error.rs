@@ -0,0 +1,116 @@
+//! Error types used throughout the [`super`] module.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+use core::fmt;
+#[cfg(feature = "std")]
+use std::fmt;
+
+/// Errors that occur when instantiating a [`super::RouteRequestGenerator`] or
+/// [`super::RouteResponseParser`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Error))]
+#[cfg_attr(feature = "uniffi", uniffi(flat_error))]
+pub enum InstantiationError {
+    /// The options provided to the constructor could not be parsed as valid JSON.
+    InvalidOptionsJson,
+    /// The requested polyline precision isn't one OSRM supports encoding geometry at.
+    ///
+    /// OSRM's `geometries` parameter only has encodings for precision 5 (`polyline`) and
+    /// precision 6 (`polyline6`).
+    UnsupportedPolylinePrecision { precision: u32 },
+}
+
+impl fmt::Display for InstantiationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidOptionsJson => write!(f, "the options provided were not valid JSON"),
+            Self::UnsupportedPolylinePrecision { precision } => write!(
+                f,
+                "OSRM only supports polyline precision 5 or 6, got {precision}"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InstantiationError {}
+
+/// Errors that occur while generating a request to a routing backend.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Error))]
+#[cfg_attr(feature = "uniffi", uniffi(flat_error))]
+pub enum RoutingRequestGenerationError {
+    /// The set of waypoints provided was not sufficient to generate a valid request.
+    ///
+    /// This is a common and recoverable error that platform code may wish to handle
+    /// by prompting the user to add another waypoint.
+    NotEnoughWaypoints,
+    /// The request body (or a portion of it) could not be serialized to, or parsed from,
+    /// JSON (ex: `options_json` in
+    /// [`RouteRequestGenerator::generate_request_with_options`](super::RouteRequestGenerator::generate_request_with_options)).
+    JsonError,
+    /// A backend index was requested that doesn't correspond to any configured backend
+    /// (ex: in [`FallbackRouteAdapter`](super::fallback::FallbackRouteAdapter)).
+    InvalidBackendIndex,
+    /// A trace attributes request was made on a [`RouteAdapter`](super::RouteAdapter) that
+    /// wasn't configured with a trace adapter.
+    TraceAttributesNotConfigured,
+}
+
+impl fmt::Display for RoutingRequestGenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughWaypoints => write!(f, "not enough waypoints were provided"),
+            Self::InvalidBackendIndex => {
+                write!(f, "no backend is configured at the requested index")
+            }
+            Self::TraceAttributesNotConfigured => {
+                write!(
+                    f,
+                    "this RouteAdapter was not configured with a trace adapter"
+                )
+            }
+            Self::JsonError => write!(
+                f,
+                "failed to serialize the request body as JSON, or to parse the provided options JSON"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RoutingRequestGenerationError {}
+
+/// Errors that occur while parsing a response from a routing backend.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Error))]
+#[cfg_attr(feature = "uniffi", uniffi(flat_error))]
+pub enum ParsingError {
+    /// The response could not be parsed as the expected format.
+    ParseError { error: String },
+    /// The backend reported an error rather than a set of routes.
+    ///
+    /// `error` is the backend-reported error message, when available.
+    ///
+    /// [`RouteAdapter::parse_trace_response`](super::RouteAdapter::parse_trace_response) also
+    /// returns this variant, without any backend involved, when the adapter wasn't configured
+    /// with a trace adapter.
+    ErrorStatusCode { error: String },
+}
+
+impl fmt::Display for ParsingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ParseError { error } => write!(f, "failed to parse response: {error}"),
+            Self::ErrorStatusCode { error } => {
+                write!(f, "routing backend reported an error: {error}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParsingError {}
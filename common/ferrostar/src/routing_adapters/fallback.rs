@@ -0,0 +1,219 @@
+//! A composite [`RouteAdapter`] that falls through an ordered list of backends.
+
+use crate::models::{Route, UserLocation, Waypoint};
+use crate::routing_adapters::error::RoutingRequestGenerationError;
+use crate::routing_adapters::{RouteAdapter, RouteRequest};
+
+#[cfg(feature = "alloc")]
+use alloc::{sync::Arc, vec::Vec};
+
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+use core::fmt;
+#[cfg(feature = "std")]
+use std::fmt;
+
+/// What a [`FallbackRouteAdapter`] caller should do after a backend's response failed to
+/// produce any routes.
+///
+/// This is returned as the `Err` variant of
+/// [`parse_response_or_advance`](FallbackRouteAdapter::parse_response_or_advance), which is
+/// exported via `#[uniffi::export]`, so (like every other error type in this module) it derives
+/// `uniffi::Error` rather than a plain `uniffi::Enum`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Error))]
+#[cfg_attr(feature = "uniffi", uniffi(flat_error))]
+pub enum FallbackState {
+    /// Generate a request for the backend at `next_index` and retry.
+    TryNext { next_index: u32 },
+    /// Every configured backend has been tried without success.
+    Exhausted,
+}
+
+impl fmt::Display for FallbackState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TryNext { next_index } => {
+                write!(f, "backend {next_index} should be tried next")
+            }
+            Self::Exhausted => write!(f, "every configured backend has been tried"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FallbackState {}
+
+/// Tries an ordered list of [`RouteAdapter`]s in turn, falling through to the next one if a
+/// backend's response fails to parse or yields no routes.
+///
+/// Since the request/response flow is split (the network call itself happens on the platform
+/// side), this does not perform any retrying on its own. Instead it exposes a small state
+/// machine:
+///
+/// 1. Call [`generate_request`](Self::generate_request) with `index = 0` and perform the
+///    resulting request on the platform side.
+/// 2. Pass the raw response to
+///    [`parse_response_or_advance`](Self::parse_response_or_advance) with the same `index`.
+/// 3. On `Ok(routes)`, routing succeeded. On `Err(FallbackState::TryNext { next_index })`,
+///    call `generate_request` again with `next_index` and repeat from step 1.
+///    On `Err(FallbackState::Exhausted)`, every backend has failed.
+#[cfg_attr(feature = "uniffi", derive(uniffi::Object))]
+pub struct FallbackRouteAdapter {
+    adapters: Vec<Arc<RouteAdapter>>,
+}
+
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+impl FallbackRouteAdapter {
+    /// Creates a fallback adapter which tries `adapters` in order, starting from index 0.
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn new(adapters: Vec<Arc<RouteAdapter>>) -> Self {
+        Self { adapters }
+    }
+
+    /// Generates the request for the backend at `index`.
+    pub fn generate_request(
+        &self,
+        index: u32,
+        user_location: UserLocation,
+        waypoints: Vec<Waypoint>,
+    ) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        self.adapter_at(index)?
+            .generate_request(user_location, waypoints)
+    }
+
+    /// Parses the response from the backend at `index`. On a parsing error or an empty route
+    /// list, returns the [`FallbackState`] describing what to try next, rather than an error
+    /// the caller has to distinguish from "every backend failed" itself.
+    pub fn parse_response_or_advance(
+        &self,
+        response: Vec<u8>,
+        index: u32,
+    ) -> Result<Vec<Route>, FallbackState> {
+        let Ok(adapter) = self.adapter_at(index) else {
+            return Err(FallbackState::Exhausted);
+        };
+
+        match adapter.parse_response(response) {
+            Ok(routes) if !routes.is_empty() => Ok(routes),
+            _ => match index.checked_add(1) {
+                Some(next_index) if (next_index as usize) < self.adapters.len() => {
+                    Err(FallbackState::TryNext { next_index })
+                }
+                _ => Err(FallbackState::Exhausted),
+            },
+        }
+    }
+
+    fn adapter_at(&self, index: u32) -> Result<&Arc<RouteAdapter>, RoutingRequestGenerationError> {
+        self.adapters
+            .get(index as usize)
+            .ok_or(RoutingRequestGenerationError::InvalidBackendIndex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routing_adapters::error::ParsingError;
+    use crate::routing_adapters::RouteResponseParser;
+
+    struct StaticParser {
+        result: Result<Vec<Route>, ParsingError>,
+    }
+
+    impl RouteResponseParser for StaticParser {
+        fn parse_response(&self, _response: Vec<u8>) -> Result<Vec<Route>, ParsingError> {
+            self.result.clone()
+        }
+    }
+
+    struct NoopGenerator;
+
+    impl crate::routing_adapters::RouteRequestGenerator for NoopGenerator {
+        fn generate_request(
+            &self,
+            _user_location: UserLocation,
+            _waypoints: Vec<Waypoint>,
+        ) -> Result<RouteRequest, RoutingRequestGenerationError> {
+            Ok(RouteRequest::HttpGet {
+                url: "https://example.com".into(),
+                headers: Default::default(),
+            })
+        }
+    }
+
+    fn adapter(result: Result<Vec<Route>, ParsingError>) -> Arc<RouteAdapter> {
+        Arc::new(RouteAdapter::new(
+            Arc::new(NoopGenerator),
+            Arc::new(StaticParser { result }),
+        ))
+    }
+
+    #[test]
+    fn first_backend_succeeds() {
+        let fallback = FallbackRouteAdapter::new(vec![
+            adapter(Ok(vec![Route::default()])),
+            adapter(Ok(vec![Route::default()])),
+        ]);
+
+        let routes = fallback
+            .parse_response_or_advance(Vec::new(), 0)
+            .expect("first backend should succeed");
+        assert_eq!(routes.len(), 1);
+    }
+
+    #[test]
+    fn first_fails_second_succeeds() {
+        let fallback = FallbackRouteAdapter::new(vec![
+            adapter(Err(ParsingError::ParseError {
+                error: "boom".into(),
+            })),
+            adapter(Ok(vec![Route::default()])),
+        ]);
+
+        let state = fallback
+            .parse_response_or_advance(Vec::new(), 0)
+            .expect_err("first backend should fail");
+        assert_eq!(state, FallbackState::TryNext { next_index: 1 });
+
+        let routes = fallback
+            .parse_response_or_advance(Vec::new(), 1)
+            .expect("second backend should succeed");
+        assert_eq!(routes.len(), 1);
+    }
+
+    #[test]
+    fn empty_route_list_counts_as_failure() {
+        let fallback = FallbackRouteAdapter::new(vec![
+            adapter(Ok(Vec::new())),
+            adapter(Ok(vec![Route::default()])),
+        ]);
+
+        let state = fallback
+            .parse_response_or_advance(Vec::new(), 0)
+            .expect_err("empty route list should advance");
+        assert_eq!(state, FallbackState::TryNext { next_index: 1 });
+    }
+
+    #[test]
+    fn all_backends_fail() {
+        let fallback = FallbackRouteAdapter::new(vec![
+            adapter(Err(ParsingError::ParseError {
+                error: "boom".into(),
+            })),
+            adapter(Err(ParsingError::ParseError {
+                error: "boom again".into(),
+            })),
+        ]);
+
+        let state = fallback
+            .parse_response_or_advance(Vec::new(), 0)
+            .expect_err("first backend should fail");
+        assert_eq!(state, FallbackState::TryNext { next_index: 1 });
+
+        let state = fallback
+            .parse_response_or_advance(Vec::new(), 1)
+            .expect_err("second backend should fail");
+        assert_eq!(state, FallbackState::Exhausted);
+    }
+}
@@ -0,0 +1,240 @@
+//! Middleware for cross-cutting request concerns (auth, API keys, static headers).
+//!
+//! Routing backends like Stadia and Mapbox require an API key or bearer token on every
+//! request. Rather than baking that into each [`RouteRequestGenerator`](super::RouteRequestGenerator),
+//! [`RouteAdapter`](super::RouteAdapter) applies an ordered chain of [`RequestMiddleware`] after
+//! the request is generated, so engine generators stay focused on routing semantics while
+//! auth/transport concerns compose independently.
+
+use crate::routing_adapters::error::RoutingRequestGenerationError;
+use crate::routing_adapters::RouteRequest;
+
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+use alloc::collections::BTreeMap as HashMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String, string::ToString};
+
+/// A hook for modifying a [`RouteRequest`] after it's been generated, before it's handed to
+/// the platform for execution.
+///
+/// Implementations may be either in Rust or foreign code (exported via UniFFI's
+/// `with_foreign`), so platforms can layer on custom middleware of their own.
+#[cfg_attr(feature = "uniffi", uniffi::export(with_foreign))]
+pub trait RequestMiddleware: Send + Sync {
+    /// Applies this middleware to `req`, returning the (possibly modified) request.
+    fn apply(&self, req: RouteRequest) -> Result<RouteRequest, RoutingRequestGenerationError>;
+}
+
+/// Appends a query parameter (ex: `?api_key=...`) to the request URL.
+///
+/// Only the URL is affected; request bodies are left untouched.
+#[cfg_attr(feature = "uniffi", derive(uniffi::Object))]
+pub struct QueryParamMiddleware {
+    name: String,
+    value: String,
+}
+
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+impl QueryParamMiddleware {
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn new(name: String, value: String) -> Self {
+        Self { name, value }
+    }
+}
+
+impl RequestMiddleware for QueryParamMiddleware {
+    fn apply(&self, req: RouteRequest) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        let append = |url: String| {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            format!(
+                "{url}{separator}{}={}",
+                percent_encode(&self.name),
+                percent_encode(&self.value)
+            )
+        };
+
+        Ok(match req {
+            RouteRequest::HttpGet { url, headers } => RouteRequest::HttpGet {
+                url: append(url),
+                headers,
+            },
+            RouteRequest::HttpPost { url, headers, body } => RouteRequest::HttpPost {
+                url: append(url),
+                headers,
+                body,
+            },
+        })
+    }
+}
+
+/// Percent-encodes `value` for safe inclusion as a single query string component.
+///
+/// Letters, digits, and `-_.~` pass through unchanged; everything else (including characters
+/// with special meaning in a query string, like `&`, `=`, `#`, and whitespace) is escaped as
+/// `%XX`, so a name or value containing them can't corrupt or truncate the query string.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char);
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Injects an `Authorization` header.
+#[cfg_attr(feature = "uniffi", derive(uniffi::Object))]
+pub struct AuthorizationMiddleware {
+    value: String,
+}
+
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+impl AuthorizationMiddleware {
+    /// Injects `Authorization: Bearer <token>`.
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn bearer(token: String) -> Self {
+        Self {
+            value: format!("Bearer {token}"),
+        }
+    }
+
+    /// Injects the given raw `Authorization` header value (ex: `Basic ...`).
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn raw(value: String) -> Self {
+        Self { value }
+    }
+}
+
+impl RequestMiddleware for AuthorizationMiddleware {
+    fn apply(&self, req: RouteRequest) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        Ok(insert_header(
+            req,
+            "Authorization".to_string(),
+            self.value.clone(),
+        ))
+    }
+}
+
+/// Injects an arbitrary, fixed set of headers.
+#[cfg_attr(feature = "uniffi", derive(uniffi::Object))]
+pub struct StaticHeadersMiddleware {
+    headers: HashMap<String, String>,
+}
+
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+impl StaticHeadersMiddleware {
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn new(headers: HashMap<String, String>) -> Self {
+        Self { headers }
+    }
+}
+
+impl RequestMiddleware for StaticHeadersMiddleware {
+    fn apply(&self, req: RouteRequest) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        let mut req = req;
+        for (key, value) in &self.headers {
+            req = insert_header(req, key.clone(), value.clone());
+        }
+        Ok(req)
+    }
+}
+
+fn insert_header(req: RouteRequest, key: String, value: String) -> RouteRequest {
+    match req {
+        RouteRequest::HttpGet { url, mut headers } => {
+            headers.insert(key, value);
+            RouteRequest::HttpGet { url, headers }
+        }
+        RouteRequest::HttpPost {
+            url,
+            mut headers,
+            body,
+        } => {
+            headers.insert(key, value);
+            RouteRequest::HttpPost { url, headers, body }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(url: &str) -> RouteRequest {
+        RouteRequest::HttpGet {
+            url: url.to_string(),
+            headers: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn query_param_middleware_appends_with_question_mark_when_absent() {
+        let req = QueryParamMiddleware::new("api_key".into(), "secret".into())
+            .apply(get("https://example.com/route"))
+            .expect("middleware should not fail");
+
+        let RouteRequest::HttpGet { url, .. } = req else {
+            panic!("expected HttpGet");
+        };
+        assert_eq!(url, "https://example.com/route?api_key=secret");
+    }
+
+    #[test]
+    fn query_param_middleware_appends_with_ampersand_when_present() {
+        let req = QueryParamMiddleware::new("api_key".into(), "secret".into())
+            .apply(get("https://example.com/route?units=km"))
+            .expect("middleware should not fail");
+
+        let RouteRequest::HttpGet { url, .. } = req else {
+            panic!("expected HttpGet");
+        };
+        assert_eq!(url, "https://example.com/route?units=km&api_key=secret");
+    }
+
+    #[test]
+    fn query_param_middleware_percent_encodes_name_and_value() {
+        let req = QueryParamMiddleware::new("api key".into(), "a&b=c#d".into())
+            .apply(get("https://example.com/route"))
+            .expect("middleware should not fail");
+
+        let RouteRequest::HttpGet { url, .. } = req else {
+            panic!("expected HttpGet");
+        };
+        assert_eq!(url, "https://example.com/route?api%20key=a%26b%3Dc%23d");
+    }
+
+    #[test]
+    fn authorization_middleware_injects_bearer_header() {
+        let req = AuthorizationMiddleware::bearer("abc123".into())
+            .apply(get("https://example.com/route"))
+            .expect("middleware should not fail");
+
+        let RouteRequest::HttpGet { headers, .. } = req else {
+            panic!("expected HttpGet");
+        };
+        assert_eq!(
+            headers.get("Authorization"),
+            Some(&"Bearer abc123".to_string())
+        );
+    }
+
+    #[test]
+    fn static_headers_middleware_injects_every_header() {
+        let headers_to_inject = HashMap::from([("X-Api-Version".to_string(), "2".to_string())]);
+
+        let req = StaticHeadersMiddleware::new(headers_to_inject)
+            .apply(get("https://example.com/route"))
+            .expect("middleware should not fail");
+
+        let RouteRequest::HttpGet { headers, .. } = req else {
+            panic!("expected HttpGet");
+        };
+        assert_eq!(headers.get("X-Api-Version"), Some(&"2".to_string()));
+    }
+}
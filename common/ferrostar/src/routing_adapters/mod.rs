@@ -48,13 +48,18 @@ use serde_json::json;
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
 #[cfg(feature = "alloc")]
-use alloc::{string::String, sync::Arc, vec::Vec};
+use alloc::{string::String, sync::Arc, vec, vec::Vec};
 
-use crate::routing_adapters::osrm::OsrmResponseParser;
-use crate::routing_adapters::valhalla::ValhallaHttpRequestGenerator;
+use crate::routing_adapters::middleware::{QueryParamMiddleware, RequestMiddleware};
+use crate::routing_adapters::osrm::{OsrmHttpRequestGenerator, OsrmResponseParser};
+use crate::routing_adapters::trace_attributes::{TraceAttributes, TraceAttributesAdapter};
+use crate::routing_adapters::valhalla::{CostingOptions, ValhallaHttpRequestGenerator};
 
 pub mod error;
+pub mod fallback;
+pub mod middleware;
 pub mod osrm;
+pub mod trace_attributes;
 pub mod valhalla;
 
 /// A route request generated by a [`RouteRequestGenerator`].
@@ -95,7 +100,28 @@ pub trait RouteRequestGenerator: Send + Sync {
         waypoints: Vec<Waypoint>,
     ) -> Result<RouteRequest, RoutingRequestGenerationError>;
 
-    // TODO: "Trace attributes" request method? Maybe in a separate trait?
+    /// Generates a request exactly like [`generate_request`](Self::generate_request),
+    /// but allows per-request overrides (ex: "avoid tolls just this once") to be layered on
+    /// top of whatever was configured at construction time.
+    ///
+    /// `options_json` is a raw JSON object string (ex: `{"use_highways": 0.0}`), following the
+    /// same convention as [`ValhallaHttpRequestGenerator::with_costing_options_json`]: a
+    /// `HashMap<String, serde_json::Value>` can't cross the UniFFI boundary this trait is
+    /// exported over, since `serde_json::Value` has no UniFFI conversion impls.
+    ///
+    /// The default implementation ignores `options_json` and delegates to `generate_request`,
+    /// so implementations which don't support per-request overrides get this for free.
+    /// [`valhalla::ValhallaHttpRequestGenerator`] deep-merges the parsed options into the
+    /// `costing_options.<profile>` object of the generated request body.
+    fn generate_request_with_options(
+        &self,
+        user_location: UserLocation,
+        waypoints: Vec<Waypoint>,
+        options_json: Option<String>,
+    ) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        let _ = options_json;
+        self.generate_request(user_location, waypoints)
+    }
 }
 
 /// A generic interface describing any object capable of parsing a response from a routing
@@ -122,8 +148,10 @@ pub trait RouteResponseParser: Send + Sync {
 /// In the future, we may provide additional methods or conveniences, and this
 /// indirection leaves the design open to such changes without necessarily breaking source
 /// compatibility.
-/// One such possible extension would be the ability to fetch more detailed attributes in real time.
-/// This is supported by the Valhalla stack, among others.
+/// One such extension is fetching more detailed attributes in real time: a [`RouteAdapter`]
+/// may optionally be configured with a [`TraceAttributesAdapter`], which wraps the
+/// backend-specific request/response flow for map-matching a GPS trace into road metadata
+/// (speed limits, road class, etc.) that can enrich the active route mid-navigation.
 ///
 /// Ideas  welcome re: how to signal compatibility between request generators and response parsers.
 /// I don't think we can do this in the type system, since one of the reasons for the split design
@@ -133,6 +161,8 @@ pub trait RouteResponseParser: Send + Sync {
 pub struct RouteAdapter {
     request_generator: Arc<dyn RouteRequestGenerator>,
     response_parser: Arc<dyn RouteResponseParser>,
+    middleware: Vec<Arc<dyn RequestMiddleware>>,
+    trace_adapter: Option<Arc<TraceAttributesAdapter>>,
 }
 
 #[cfg_attr(feature = "uniffi", uniffi::export)]
@@ -141,10 +171,43 @@ impl RouteAdapter {
     pub fn new(
         request_generator: Arc<dyn RouteRequestGenerator>,
         response_parser: Arc<dyn RouteResponseParser>,
+    ) -> Self {
+        Self::new_with_middleware(request_generator, response_parser, Vec::new())
+    }
+
+    /// Creates an adapter with an ordered chain of [`RequestMiddleware`] applied to every
+    /// request generated via `request_generator`, before it's handed to the platform for
+    /// execution. This is how auth/transport concerns (API keys, bearer tokens, static
+    /// headers) compose independently of the routing engine's own request generation.
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn new_with_middleware(
+        request_generator: Arc<dyn RouteRequestGenerator>,
+        response_parser: Arc<dyn RouteResponseParser>,
+        middleware: Vec<Arc<dyn RequestMiddleware>>,
+    ) -> Self {
+        Self {
+            request_generator,
+            response_parser,
+            middleware,
+            trace_adapter: None,
+        }
+    }
+
+    /// Creates an adapter additionally configured with a [`TraceAttributesAdapter`], enabling
+    /// [`generate_trace_request`](Self::generate_trace_request) and
+    /// [`parse_trace_response`](Self::parse_trace_response).
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn new_with_trace_adapter(
+        request_generator: Arc<dyn RouteRequestGenerator>,
+        response_parser: Arc<dyn RouteResponseParser>,
+        middleware: Vec<Arc<dyn RequestMiddleware>>,
+        trace_adapter: Arc<TraceAttributesAdapter>,
     ) -> Self {
         Self {
             request_generator,
             response_parser,
+            middleware,
+            trace_adapter: Some(trace_adapter),
         }
     }
 
@@ -163,6 +226,71 @@ impl RouteAdapter {
         Ok(Self::new(request_generator, response_parser))
     }
 
+    /// Creates a Valhalla HTTP adapter using typed, construction-time
+    /// [`CostingOptions`](crate::routing_adapters::valhalla::CostingOptions) rather than a raw
+    /// JSON string, so UniFFI (Swift/Kotlin) callers get autocompletion and compile-time safety
+    /// for the common costing parameters. The WASM wrapper (`JsRouteAdapter`) does not expose
+    /// this constructor.
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn new_valhalla_http_with_costing_options(
+        endpoint_url: String,
+        profile: String,
+        costing_options: CostingOptions,
+    ) -> Result<Self, InstantiationError> {
+        let request_generator = Arc::new(ValhallaHttpRequestGenerator::with_costing_options(
+            endpoint_url,
+            profile,
+            costing_options,
+        )?);
+        let response_parser = Arc::new(OsrmResponseParser::new(6));
+        Ok(Self::new(request_generator, response_parser))
+    }
+
+    /// Creates a Valhalla HTTP adapter like [`new_valhalla_http`](Self::new_valhalla_http),
+    /// additionally appending `?api_key=<api_key>` to every generated request via a
+    /// [`QueryParamMiddleware`]. This is the common case for keyed Valhalla deployments
+    /// (ex: Stadia Maps), surfaced directly so bound platforms don't need to assemble the
+    /// middleware chain themselves.
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn new_valhalla_http_with_api_key(
+        endpoint_url: String,
+        profile: String,
+        costing_options_json: Option<String>,
+        api_key: String,
+    ) -> Result<Self, InstantiationError> {
+        let request_generator = Arc::new(ValhallaHttpRequestGenerator::with_costing_options_json(
+            endpoint_url,
+            profile,
+            costing_options_json,
+        )?);
+        let response_parser = Arc::new(OsrmResponseParser::new(6));
+        let middleware: Vec<Arc<dyn RequestMiddleware>> = vec![Arc::new(
+            QueryParamMiddleware::new("api_key".into(), api_key),
+        )];
+        Ok(Self::new_with_middleware(
+            request_generator,
+            response_parser,
+            middleware,
+        ))
+    }
+
+    /// Creates an adapter for a self-hosted OSRM `/route/v1` HTTP API, using
+    /// [`OsrmHttpRequestGenerator`] paired with the matching [`OsrmResponseParser`].
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn new_osrm_http(
+        endpoint_url: String,
+        profile: String,
+        polyline_precision: u32,
+    ) -> Result<Self, InstantiationError> {
+        let request_generator = Arc::new(OsrmHttpRequestGenerator::new(
+            endpoint_url,
+            profile,
+            polyline_precision,
+        )?);
+        let response_parser = Arc::new(OsrmResponseParser::new(polyline_precision));
+        Ok(Self::new(request_generator, response_parser))
+    }
+
     //
     // Proxied implementation methods.
     //
@@ -172,13 +300,74 @@ impl RouteAdapter {
         user_location: UserLocation,
         waypoints: Vec<Waypoint>,
     ) -> Result<RouteRequest, RoutingRequestGenerationError> {
-        self.request_generator
-            .generate_request(user_location, waypoints)
+        let req = self
+            .request_generator
+            .generate_request(user_location, waypoints)?;
+        self.apply_middleware(req)
+    }
+
+    /// Generates a request, applying one-off `options_json` on top of whatever the underlying
+    /// generator was configured with at construction time.
+    ///
+    /// See [`RouteRequestGenerator::generate_request_with_options`] for details; generators
+    /// that don't support per-request overrides simply ignore `options_json`.
+    pub fn generate_request_with_options(
+        &self,
+        user_location: UserLocation,
+        waypoints: Vec<Waypoint>,
+        options_json: Option<String>,
+    ) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        let req = self.request_generator.generate_request_with_options(
+            user_location,
+            waypoints,
+            options_json,
+        )?;
+        self.apply_middleware(req)
     }
 
     pub fn parse_response(&self, response: Vec<u8>) -> Result<Vec<Route>, ParsingError> {
         self.response_parser.parse_response(response)
     }
+
+    /// Generates a trace attributes (map-matching) request, if this adapter was configured
+    /// with a [`TraceAttributesAdapter`] via
+    /// [`new_with_trace_adapter`](Self::new_with_trace_adapter).
+    pub fn generate_trace_request(
+        &self,
+        shape: Vec<UserLocation>,
+        requested_attributes: Vec<String>,
+    ) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        match &self.trace_adapter {
+            Some(trace_adapter) => {
+                let req = trace_adapter.generate_trace_request(shape, requested_attributes)?;
+                self.apply_middleware(req)
+            }
+            None => Err(RoutingRequestGenerationError::TraceAttributesNotConfigured),
+        }
+    }
+
+    /// Parses a trace attributes response produced by
+    /// [`generate_trace_request`](Self::generate_trace_request).
+    pub fn parse_trace_response(
+        &self,
+        response: Vec<u8>,
+    ) -> Result<Vec<TraceAttributes>, ParsingError> {
+        match &self.trace_adapter {
+            Some(trace_adapter) => trace_adapter.parse_trace_response(response),
+            None => Err(ParsingError::ErrorStatusCode {
+                error: "this RouteAdapter was not configured with a trace adapter".into(),
+            }),
+        }
+    }
+
+    fn apply_middleware(
+        &self,
+        req: RouteRequest,
+    ) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        self.middleware
+            .iter()
+            .try_fold(req, |req, middleware| middleware.apply(req))
+    }
 }
 
 /// JavaScript wrapper for `RouteAdapter`.
@@ -190,7 +379,6 @@ pub struct JsRouteAdapter(RouteAdapter);
 #[wasm_bindgen(js_class = RouteAdapter)]
 impl JsRouteAdapter {
     /// Creates a new RouteAdapter with a Valhalla HTTP request generator and an OSRM response parser.
-    /// At the moment, this is the only supported combination.
     #[wasm_bindgen(constructor)]
     pub fn new(
         endpoint_url: String,
@@ -203,6 +391,18 @@ impl JsRouteAdapter {
         // TODO: We should have a better error handling strategy here. Same for the other methods.
     }
 
+    /// Creates a new RouteAdapter for a self-hosted OSRM `/route/v1` HTTP API.
+    #[wasm_bindgen(js_name = newOsrmHttp)]
+    pub fn new_osrm_http(
+        endpoint_url: String,
+        profile: String,
+        polyline_precision: u32,
+    ) -> Result<JsRouteAdapter, JsValue> {
+        RouteAdapter::new_osrm_http(endpoint_url, profile, polyline_precision)
+            .map(JsRouteAdapter)
+            .map_err(|e| JsValue::from_str(&format!("{}", e)))
+    }
+
     #[wasm_bindgen(js_name = generateRequest)]
     pub fn generate_request(
         &self,
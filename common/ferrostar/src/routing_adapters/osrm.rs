@@ -0,0 +1,231 @@
+//! Generic support for the [OSRM API](http://project-osrm.org/docs/v5.24.0/api/) and APIs
+//! which are substantially similar (ex: Valhalla's `osrm` response format).
+
+use crate::models::{Route, UserLocation, Waypoint};
+use crate::routing_adapters::error::{
+    InstantiationError, ParsingError, RoutingRequestGenerationError,
+};
+use crate::routing_adapters::{RouteRequest, RouteRequestGenerator, RouteResponseParser};
+
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+use alloc::collections::BTreeMap as HashMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String, string::ToString, vec, vec::Vec};
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct OsrmResponse {
+    code: String,
+    message: Option<String>,
+    routes: Option<Vec<Route>>,
+}
+
+/// A response parser for OSRM-compatible routing backends.
+///
+/// This should work with OSRM, Valhalla (when configured with `format: osrm`), and
+/// other compatible backends which follow the same route geometry encoding conventions.
+pub struct OsrmResponseParser {
+    polyline_precision: u32,
+}
+
+impl OsrmResponseParser {
+    pub fn new(polyline_precision: u32) -> Self {
+        Self { polyline_precision }
+    }
+}
+
+impl RouteResponseParser for OsrmResponseParser {
+    fn parse_response(&self, response: Vec<u8>) -> Result<Vec<Route>, ParsingError> {
+        let res: OsrmResponse =
+            serde_json::from_slice(&response).map_err(|error| ParsingError::ParseError {
+                error: error.to_string(),
+            })?;
+
+        if res.code != "Ok" {
+            return Err(ParsingError::ErrorStatusCode {
+                error: res.message.unwrap_or(res.code),
+            });
+        }
+
+        // `polyline_precision` is retained on the parser (rather than baked into
+        // deserialization) so that future iterations can use it to decode geometry
+        // that arrives pre-encoded rather than already resolved into coordinates.
+        let _ = self.polyline_precision;
+
+        res.routes.ok_or_else(|| ParsingError::ParseError {
+            error: "Response contained no routes".to_string(),
+        })
+    }
+}
+
+/// A [`RouteRequestGenerator`] for the self-hosted OSRM `/route/v1` HTTP API.
+///
+/// Pair this with an [`OsrmResponseParser`], since OSRM's own response format is exactly
+/// what it expects; [`RouteAdapter::new_osrm_http`](super::RouteAdapter::new_osrm_http) does
+/// this pairing for you.
+pub struct OsrmHttpRequestGenerator {
+    endpoint_url: String,
+    profile: String,
+    geometries: &'static str,
+}
+
+impl OsrmHttpRequestGenerator {
+    /// Creates a generator requesting geometry at `polyline_precision`, which must be `5`
+    /// (OSRM's own default, `geometries=polyline`) or `6` (`geometries=polyline6`); any other
+    /// value is rejected, since OSRM has no encoding for it.
+    pub fn new(
+        endpoint_url: String,
+        profile: String,
+        polyline_precision: u32,
+    ) -> Result<Self, InstantiationError> {
+        let geometries = match polyline_precision {
+            5 => "polyline",
+            6 => "polyline6",
+            precision => {
+                return Err(InstantiationError::UnsupportedPolylinePrecision { precision })
+            }
+        };
+
+        Ok(Self {
+            endpoint_url,
+            profile,
+            geometries,
+        })
+    }
+}
+
+impl RouteRequestGenerator for OsrmHttpRequestGenerator {
+    fn generate_request(
+        &self,
+        user_location: UserLocation,
+        waypoints: Vec<Waypoint>,
+    ) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        if waypoints.is_empty() {
+            return Err(RoutingRequestGenerationError::NotEnoughWaypoints);
+        }
+
+        let mut coordinates = vec![format!(
+            "{},{}",
+            user_location.coordinates.lng, user_location.coordinates.lat
+        )];
+        coordinates.extend(
+            waypoints
+                .iter()
+                .map(|waypoint| format!("{},{}", waypoint.coordinate.lng, waypoint.coordinate.lat)),
+        );
+
+        // OSRM's `bearings` parameter is per-coordinate; we only know a heading for the
+        // user's own location, so every waypoint gets an empty (unconstrained) entry.
+        let mut bearings = vec![match &user_location.course_over_ground {
+            Some(course) => format!("{},{}", course.degrees, course.accuracy),
+            None => String::new(),
+        }];
+        bearings.extend(waypoints.iter().map(|_| String::new()));
+
+        let url = format!(
+            "{}/route/v1/{}/{}?overview=full&geometries={}&steps=true&alternatives=true&annotations=true&bearings={}",
+            self.endpoint_url,
+            self.profile,
+            coordinates.join(";"),
+            self.geometries,
+            bearings.join(";"),
+        );
+
+        Ok(RouteRequest::HttpGet {
+            url,
+            headers: HashMap::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CourseOverGround, GeographicCoordinate, WaypointKind};
+
+    fn user_location(lat: f64, lng: f64, course: Option<CourseOverGround>) -> UserLocation {
+        UserLocation {
+            coordinates: GeographicCoordinate { lat, lng },
+            horizontal_accuracy: 0.0,
+            course_over_ground: course,
+            timestamp: std::time::SystemTime::UNIX_EPOCH,
+            speed: None,
+        }
+    }
+
+    fn waypoint(lat: f64, lng: f64) -> Waypoint {
+        Waypoint {
+            coordinate: GeographicCoordinate { lat, lng },
+            kind: WaypointKind::Break,
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_polyline_precision() {
+        let error =
+            OsrmHttpRequestGenerator::new("https://osrm.example.com".into(), "car".into(), 7)
+                .expect_err("precision 7 has no OSRM geometries encoding");
+        assert_eq!(
+            error,
+            InstantiationError::UnsupportedPolylinePrecision { precision: 7 }
+        );
+    }
+
+    #[test]
+    fn url_uses_polyline_for_precision_5() {
+        let generator =
+            OsrmHttpRequestGenerator::new("https://osrm.example.com".into(), "car".into(), 5)
+                .expect("precision 5 is supported");
+
+        let request = generator
+            .generate_request(user_location(1.0, 2.0, None), vec![waypoint(3.0, 4.0)])
+            .expect("request should be generated");
+
+        let RouteRequest::HttpGet { url, .. } = request else {
+            panic!("expected an HttpGet request");
+        };
+        assert!(url.contains("geometries=polyline&"));
+        assert!(!url.contains("polyline6"));
+    }
+
+    #[test]
+    fn url_uses_polyline6_for_precision_6_and_encodes_coordinates_and_bearings() {
+        let generator =
+            OsrmHttpRequestGenerator::new("https://osrm.example.com".into(), "car".into(), 6)
+                .expect("precision 6 is supported");
+
+        let course = CourseOverGround {
+            degrees: 90,
+            accuracy: 5,
+        };
+        let request = generator
+            .generate_request(
+                user_location(1.0, 2.0, Some(course)),
+                vec![waypoint(3.0, 4.0)],
+            )
+            .expect("request should be generated");
+
+        let RouteRequest::HttpGet { url, .. } = request else {
+            panic!("expected an HttpGet request");
+        };
+        assert!(url.starts_with("https://osrm.example.com/route/v1/car/2,1;4,3?"));
+        assert!(url.contains("geometries=polyline6"));
+        assert!(url.contains("bearings=90,5;"));
+    }
+
+    #[test]
+    fn rejects_empty_waypoints() {
+        let generator =
+            OsrmHttpRequestGenerator::new("https://osrm.example.com".into(), "car".into(), 6)
+                .expect("precision 6 is supported");
+
+        let error = generator
+            .generate_request(user_location(1.0, 2.0, None), Vec::new())
+            .expect_err("no waypoints should fail");
+        assert_eq!(error, RoutingRequestGenerationError::NotEnoughWaypoints);
+    }
+}
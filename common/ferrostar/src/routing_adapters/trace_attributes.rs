@@ -0,0 +1,95 @@
+//! Real-time road metadata via map-matching ("trace attributes") requests.
+//!
+//! This is a separate request/response flow from the routing one in [`super`]: rather than
+//! producing a route between waypoints, a trace attributes request matches a recorded (or
+//! live) GPS trace against the road network and reports per-edge metadata, so the active
+//! route can be enriched with live data like speed limits mid-navigation.
+
+use crate::models::UserLocation;
+use crate::routing_adapters::error::{ParsingError, RoutingRequestGenerationError};
+use crate::routing_adapters::RouteRequest;
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, sync::Arc, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+
+/// Per-edge road metadata matched against a GPS trace by a [`TraceAttributesParser`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct TraceAttributes {
+    /// The index into the requested `shape` that this edge's metadata corresponds to.
+    pub matched_point_index: u32,
+    pub speed_limit_kph: Option<f64>,
+    pub road_class: Option<String>,
+    pub surface: Option<String>,
+    pub names: Vec<String>,
+}
+
+/// Generates a trace attributes (map-matching) request for a recorded or live GPS trace.
+///
+/// This is a separate trait from [`RouteRequestGenerator`](super::RouteRequestGenerator)
+/// since not every backend supports it, and it serves a different purpose: enriching an
+/// already-active route rather than generating a new one.
+#[cfg_attr(feature = "uniffi", uniffi::export(with_foreign))]
+pub trait TraceAttributesGenerator: Send + Sync {
+    /// Generates a request matching `shape` against the road network, requesting the
+    /// backend-specific `requested_attributes` (ex: `"speed"`, `"road_class"`) for each
+    /// matched edge.
+    fn generate_trace_request(
+        &self,
+        shape: Vec<UserLocation>,
+        requested_attributes: Vec<String>,
+    ) -> Result<RouteRequest, RoutingRequestGenerationError>;
+}
+
+/// Parses a trace attributes response into structured [`TraceAttributes`].
+#[cfg_attr(feature = "uniffi", uniffi::export(with_foreign))]
+pub trait TraceAttributesParser: Send + Sync {
+    fn parse_trace_response(&self, response: Vec<u8>)
+        -> Result<Vec<TraceAttributes>, ParsingError>;
+}
+
+/// Bridges between the common core and a routing backend's trace attributes (map-matching)
+/// API, mirroring how [`RouteAdapter`](super::RouteAdapter) bridges the routing request flow.
+#[cfg_attr(feature = "uniffi", derive(uniffi::Object))]
+pub struct TraceAttributesAdapter {
+    generator: Arc<dyn TraceAttributesGenerator>,
+    parser: Arc<dyn TraceAttributesParser>,
+}
+
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+impl TraceAttributesAdapter {
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn new(
+        generator: Arc<dyn TraceAttributesGenerator>,
+        parser: Arc<dyn TraceAttributesParser>,
+    ) -> Self {
+        Self { generator, parser }
+    }
+
+    /// Creates an adapter backed by Valhalla's `trace_attributes` endpoint.
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn new_valhalla_http(endpoint_url: String, profile: String) -> Self {
+        let valhalla = Arc::new(
+            crate::routing_adapters::valhalla::ValhallaTraceAttributes::new(endpoint_url, profile),
+        );
+        Self::new(valhalla.clone(), valhalla)
+    }
+
+    pub fn generate_trace_request(
+        &self,
+        shape: Vec<UserLocation>,
+        requested_attributes: Vec<String>,
+    ) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        self.generator
+            .generate_trace_request(shape, requested_attributes)
+    }
+
+    pub fn parse_trace_response(
+        &self,
+        response: Vec<u8>,
+    ) -> Result<Vec<TraceAttributes>, ParsingError> {
+        self.parser.parse_trace_response(response)
+    }
+}
@@ -0,0 +1,393 @@
+//! Support for the [Valhalla](https://valhalla.github.io/valhalla/) route engine.
+//!
+//! Valhalla can be configured to return a response in OSRM's format,
+//! so for routing this module only defines a [`RouteRequestGenerator`] (plus its typed
+//! [`CostingOptions`]); pair it with an [`OsrmResponseParser`](super::osrm::OsrmResponseParser).
+//! It also defines [`ValhallaTraceAttributes`], which drives Valhalla's `trace_attributes`
+//! endpoint.
+
+use crate::models::{UserLocation, Waypoint};
+use crate::routing_adapters::error::{
+    InstantiationError, ParsingError, RoutingRequestGenerationError,
+};
+use crate::routing_adapters::trace_attributes::{
+    TraceAttributes, TraceAttributesGenerator, TraceAttributesParser,
+};
+use crate::routing_adapters::{RouteRequest, RouteRequestGenerator};
+
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+use alloc::collections::BTreeMap as HashMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String, string::ToString, vec, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Typed costing options for Valhalla's routing engine.
+///
+/// Covers the costing parameters shared by Valhalla's auto, bicycle, and pedestrian costing
+/// models. All fields are optional; an omitted field falls back to Valhalla's own default for
+/// the profile in use. Parameters not covered by a named field (or specific to a less common
+/// profile) can still be set via `extra_json`, which is merged in alongside the named fields.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct CostingOptions {
+    /// Penalty (in seconds) applied to each maneuver, to favor routes with fewer turns.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maneuver_penalty: Option<f64>,
+    /// A range of values from 0 to 1, where 0 avoids highways and 1 has no aversion to them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_highways: Option<f64>,
+    /// A range of values from 0 to 1, where 0 avoids tolls and 1 has no aversion to them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_tolls: Option<f64>,
+    /// A range of values from 0 to 1, where 0 avoids ferries and 1 has no aversion to them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_ferry: Option<f64>,
+    /// A range of values from 0 to 1, where 0 avoids hills and 1 has no aversion to them.
+    /// Applies to the bicycle and pedestrian profiles.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_hills: Option<f64>,
+    /// The Valhalla bicycle type (ex: `"Road"`, `"Hybrid"`, `"City"`, `"Mountain"`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bicycle_type: Option<String>,
+    /// Cycling speed in km/h, used by the bicycle profile.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cycling_speed: Option<f64>,
+    /// Walking speed in km/h, used by the pedestrian profile.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub walking_speed: Option<f64>,
+    /// A factor that modifies the cost of walkways, used by the pedestrian profile.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub walkway_factor: Option<f64>,
+    /// Additional Valhalla costing parameters not covered by the named fields above, as a raw
+    /// JSON object string (ex: `{"disable_hills": true}`).
+    ///
+    /// This is merged in alongside the named fields when the request is built, so it doubles
+    /// as an escape hatch for engine-specific or less common parameters. Unlike the named
+    /// fields, it crosses the UniFFI boundary as a plain string rather than a
+    /// `HashMap<String, serde_json::Value>`, since `serde_json::Value` has no UniFFI
+    /// conversion impls.
+    #[serde(skip)]
+    pub extra_json: Option<String>,
+}
+
+/// A [`RouteRequestGenerator`] for Valhalla's `/route` HTTP API.
+///
+/// Pair this with an [`OsrmResponseParser`](super::osrm::OsrmResponseParser),
+/// as Valhalla can be configured to emit OSRM-compatible responses (`"format": "osrm"`),
+/// which is what this generator requests.
+pub struct ValhallaHttpRequestGenerator {
+    endpoint_url: String,
+    profile: String,
+    costing_options: Option<Value>,
+}
+
+impl ValhallaHttpRequestGenerator {
+    pub fn new(endpoint_url: String, profile: String) -> Self {
+        Self {
+            endpoint_url,
+            profile,
+            costing_options: None,
+        }
+    }
+
+    /// Creates a generator with construction-time costing options, given as a raw JSON object
+    /// string (ex: `{"use_highways": 0.0}`).
+    ///
+    /// These are merged into the `costing_options.<profile>` key of every generated request.
+    /// Per-request overrides may still be layered on top via
+    /// [`generate_request_with_options`](RouteRequestGenerator::generate_request_with_options).
+    pub fn with_costing_options_json(
+        endpoint_url: String,
+        profile: String,
+        costing_options_json: Option<String>,
+    ) -> Result<Self, InstantiationError> {
+        let costing_options = costing_options_json
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(|_| InstantiationError::InvalidOptionsJson)?;
+
+        Ok(Self {
+            endpoint_url,
+            profile,
+            costing_options,
+        })
+    }
+
+    /// Creates a generator with typed, construction-time costing options.
+    ///
+    /// Unlike [`with_costing_options_json`](Self::with_costing_options_json), the common costing
+    /// parameters are typed fields, while [`CostingOptions::extra_json`] remains available for
+    /// anything uncommon. UniFFI callers reach this via
+    /// [`RouteAdapter::new_valhalla_http_with_costing_options`](super::RouteAdapter::new_valhalla_http_with_costing_options);
+    /// the WASM wrapper has no constructor that takes [`CostingOptions`].
+    pub fn with_costing_options(
+        endpoint_url: String,
+        profile: String,
+        costing_options: CostingOptions,
+    ) -> Result<Self, InstantiationError> {
+        let extra_json = costing_options.extra_json.clone();
+        let mut costing_options = serde_json::to_value(costing_options)
+            .map_err(|_| InstantiationError::InvalidOptionsJson)?;
+
+        if let Some(extra_json) = extra_json {
+            let extra = serde_json::from_str(&extra_json)
+                .map_err(|_| InstantiationError::InvalidOptionsJson)?;
+            Self::deep_merge(&mut costing_options, &extra);
+        }
+
+        Ok(Self {
+            endpoint_url,
+            profile,
+            costing_options: Some(costing_options),
+        })
+    }
+
+    fn request_body(
+        &self,
+        user_location: UserLocation,
+        waypoints: Vec<Waypoint>,
+        costing_options: Value,
+    ) -> Value {
+        let mut locations = vec![json!({
+            "lat": user_location.coordinates.lat,
+            "lon": user_location.coordinates.lng,
+        })];
+        locations.extend(waypoints.iter().map(|waypoint| {
+            json!({
+                "lat": waypoint.coordinate.lat,
+                "lon": waypoint.coordinate.lng,
+            })
+        }));
+
+        json!({
+            "locations": locations,
+            "costing": self.profile,
+            "costing_options": {
+                &self.profile: costing_options,
+            },
+            "alternates": 0,
+            "format": "osrm",
+            "banner_instructions": true,
+            "voice_instructions": true,
+            "units": "km",
+        })
+    }
+
+    /// Deep-merges `overrides` into `base`, with values in `overrides` taking precedence.
+    ///
+    /// Nested objects are merged key-by-key rather than replaced wholesale;
+    /// any other value type (including arrays) is simply overwritten.
+    fn deep_merge(base: &mut Value, overrides: &Value) {
+        match (base, overrides) {
+            (Value::Object(base_map), Value::Object(overrides_map)) => {
+                for (key, overrides_value) in overrides_map {
+                    Self::deep_merge(
+                        base_map.entry(key.clone()).or_insert(Value::Null),
+                        overrides_value,
+                    );
+                }
+            }
+            (base_slot, overrides_value) => {
+                *base_slot = overrides_value.clone();
+            }
+        }
+    }
+
+    fn build_request(
+        &self,
+        user_location: UserLocation,
+        waypoints: Vec<Waypoint>,
+        per_request_options: Option<&Value>,
+    ) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        if waypoints.is_empty() {
+            return Err(RoutingRequestGenerationError::NotEnoughWaypoints);
+        }
+
+        let mut costing_options = self.costing_options.clone().unwrap_or_else(|| json!({}));
+        if let Some(overrides) = per_request_options {
+            Self::deep_merge(&mut costing_options, overrides);
+        }
+
+        let args = self.request_body(user_location, waypoints, costing_options);
+        let body =
+            serde_json::to_vec(&args).map_err(|_| RoutingRequestGenerationError::JsonError)?;
+
+        Ok(RouteRequest::HttpPost {
+            url: format!("{}/route", self.endpoint_url),
+            headers: HashMap::from([("Content-Type".to_string(), "application/json".to_string())]),
+            body,
+        })
+    }
+}
+
+impl RouteRequestGenerator for ValhallaHttpRequestGenerator {
+    fn generate_request(
+        &self,
+        user_location: UserLocation,
+        waypoints: Vec<Waypoint>,
+    ) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        self.build_request(user_location, waypoints, None)
+    }
+
+    fn generate_request_with_options(
+        &self,
+        user_location: UserLocation,
+        waypoints: Vec<Waypoint>,
+        options_json: Option<String>,
+    ) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        let overrides = options_json
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(|_| RoutingRequestGenerationError::JsonError)?;
+        self.build_request(user_location, waypoints, overrides.as_ref())
+    }
+}
+
+/// Drives Valhalla's `trace_attributes` endpoint, which map-matches a GPS trace against the
+/// road network and reports per-edge metadata like speed limits and road class.
+pub struct ValhallaTraceAttributes {
+    endpoint_url: String,
+    profile: String,
+}
+
+impl ValhallaTraceAttributes {
+    pub fn new(endpoint_url: String, profile: String) -> Self {
+        Self {
+            endpoint_url,
+            profile,
+        }
+    }
+}
+
+impl TraceAttributesGenerator for ValhallaTraceAttributes {
+    fn generate_trace_request(
+        &self,
+        shape: Vec<UserLocation>,
+        requested_attributes: Vec<String>,
+    ) -> Result<RouteRequest, RoutingRequestGenerationError> {
+        if shape.is_empty() {
+            return Err(RoutingRequestGenerationError::NotEnoughWaypoints);
+        }
+
+        let shape: Vec<Value> = shape
+            .iter()
+            .map(|point| {
+                json!({
+                    "lat": point.coordinates.lat,
+                    "lon": point.coordinates.lng,
+                })
+            })
+            .collect();
+
+        let args = json!({
+            "shape": shape,
+            "costing": self.profile,
+            "shape_match": "map_snap",
+            "filters": {
+                "attributes": requested_attributes,
+                "action": "include",
+            },
+        });
+        let body =
+            serde_json::to_vec(&args).map_err(|_| RoutingRequestGenerationError::JsonError)?;
+
+        Ok(RouteRequest::HttpPost {
+            url: format!("{}/trace_attributes", self.endpoint_url),
+            headers: HashMap::from([("Content-Type".to_string(), "application/json".to_string())]),
+            body,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ValhallaTraceAttributesResponse {
+    edges: Option<Vec<ValhallaEdge>>,
+}
+
+#[derive(Deserialize)]
+struct ValhallaEdge {
+    begin_shape_index: u32,
+    speed_limit: Option<f64>,
+    road_class: Option<String>,
+    surface: Option<String>,
+    #[serde(default)]
+    names: Vec<String>,
+}
+
+impl TraceAttributesParser for ValhallaTraceAttributes {
+    fn parse_trace_response(
+        &self,
+        response: Vec<u8>,
+    ) -> Result<Vec<TraceAttributes>, ParsingError> {
+        let res: ValhallaTraceAttributesResponse =
+            serde_json::from_slice(&response).map_err(|error| ParsingError::ParseError {
+                error: error.to_string(),
+            })?;
+
+        Ok(res
+            .edges
+            .unwrap_or_default()
+            .into_iter()
+            .map(|edge| TraceAttributes {
+                matched_point_index: edge.begin_shape_index,
+                speed_limit_kph: edge.speed_limit,
+                road_class: edge.road_class,
+                surface: edge.surface,
+                names: edge.names,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deep_merge_overrides_scalar_values() {
+        let mut base = json!({"use_highways": 0.5, "use_tolls": 1.0});
+        let overrides = json!({"use_highways": 0.0});
+
+        ValhallaHttpRequestGenerator::deep_merge(&mut base, &overrides);
+
+        assert_eq!(base, json!({"use_highways": 0.0, "use_tolls": 1.0}));
+    }
+
+    #[test]
+    fn deep_merge_merges_nested_objects_key_by_key() {
+        let mut base = json!({"bss_rent_cost": 1, "extra": {"a": 1, "b": 2}});
+        let overrides = json!({"extra": {"b": 20, "c": 3}});
+
+        ValhallaHttpRequestGenerator::deep_merge(&mut base, &overrides);
+
+        assert_eq!(
+            base,
+            json!({"bss_rent_cost": 1, "extra": {"a": 1, "b": 20, "c": 3}})
+        );
+    }
+
+    #[test]
+    fn deep_merge_replaces_arrays_wholesale() {
+        let mut base = json!({"avoid_polygons": [[1, 2]]});
+        let overrides = json!({"avoid_polygons": [[3, 4], [5, 6]]});
+
+        ValhallaHttpRequestGenerator::deep_merge(&mut base, &overrides);
+
+        assert_eq!(base, json!({"avoid_polygons": [[3, 4], [5, 6]]}));
+    }
+
+    #[test]
+    fn deep_merge_adds_new_keys() {
+        let mut base = json!({"use_highways": 0.5});
+        let overrides = json!({"use_hills": 0.2});
+
+        ValhallaHttpRequestGenerator::deep_merge(&mut base, &overrides);
+
+        assert_eq!(base, json!({"use_highways": 0.5, "use_hills": 0.2}));
+    }
+}